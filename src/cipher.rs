@@ -0,0 +1,117 @@
+use std::num::Wrapping;
+
+/// A 16-round XTEA variant keyed off the handshake bytes returned in the type-0 Info response.
+/// Every jump/dive log record the device returns is encrypted with this cipher.
+pub struct Cipher {
+    k: [u32; 4],
+}
+
+impl Cipher {
+    pub fn from_type0_bytes(bytes: &[u8]) -> Self {
+        Self {
+            k: [
+                u32::from_le_bytes([78, bytes[8], bytes[26], bytes[24]]),
+                u32::from_le_bytes([bytes[6], bytes[25], bytes[23], bytes[13]]),
+                u32::from_le_bytes([bytes[10], 117, bytes[7], bytes[22]]),
+                u32::from_le_bytes([bytes[9], bytes[11], 126, bytes[21]]),
+            ],
+        }
+    }
+
+    pub fn encrypt_single(&self, v: &[u32]) -> [u32; 2] {
+        let mut u = Wrapping(v[0]);
+        let mut u1 = Wrapping(v[1]);
+        let mut u2 = Wrapping(0u32);
+
+        for _ in 0..16 {
+            u += (((u1 << 4) ^ (u1 >> 5)) + u1) ^ (u2 + Wrapping(self.k[(u2.0 & 3) as usize]));
+            u2 += Wrapping(0x9E3779B9);
+            u1 += (((u << 4) ^ (u >> 5)) + u) ^ (u2 + Wrapping(self.k[((u2.0 >> 11) & 3) as usize]));
+        }
+
+        [u.0, u1.0]
+    }
+
+    pub fn decrypt_single(&self, v: &[u32]) -> [u32; 2] {
+        let mut u = Wrapping(v[0]);
+        let mut u1 = Wrapping(v[1]);
+        let mut u2 = Wrapping(0xE3779B90);
+
+        for _ in 0..16 {
+            u1 -= (((u << 4) ^ (u >> 5)) + u) ^ (u2 + Wrapping(self.k[((u2.0 >> 11) & 3) as usize]));
+            u2 -= Wrapping(0x9E3779B9);
+            u -= (((u1 << 4) ^ (u1 >> 5)) + u1) ^ (u2 + Wrapping(self.k[(u2.0 & 3) as usize]));
+        }
+
+        [u.0, u1.0]
+    }
+
+    pub fn encrypt(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        let len = bytes.len();
+        bytes.resize(len + if !len.is_multiple_of(32) { 32 - len % 32 } else { 0 }, 0);
+
+        let u32s: Vec<u32> = bytes
+            .chunks(4)
+            .map(|chunk| {
+                let mut b = [0u8; 4];
+                b[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(b)
+            })
+            .collect();
+
+        let pairs = u32s.chunks_exact(2);
+        pairs
+            .flat_map(|pair| {
+                let enc_pair = self.encrypt_single(pair);
+                let mut bytes = Vec::with_capacity(8);
+                bytes.extend_from_slice(&enc_pair[0].to_le_bytes());
+                bytes.extend_from_slice(&enc_pair[1].to_le_bytes());
+                bytes
+            })
+            .collect()
+    }
+
+    pub fn decrypt(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        let len = bytes.len();
+        bytes.resize(len + if !len.is_multiple_of(32) { 32 - len % 32 } else { 0 }, 0);
+
+        let u32s: Vec<u32> = bytes
+            .chunks(4)
+            .map(|chunk| {
+                let mut b = [0u8; 4];
+                b[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(b)
+            })
+            .collect();
+
+        let pairs = u32s.chunks_exact(2);
+        pairs
+            .flat_map(|pair| {
+                let enc_pair = self.decrypt_single(pair);
+                let mut bytes = Vec::with_capacity(8);
+                bytes.extend_from_slice(&enc_pair[0].to_le_bytes());
+                bytes.extend_from_slice(&enc_pair[1].to_le_bytes());
+                bytes
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TYPE0_RESPONSE: &[u8] = &[
+        0x1E, 0x00, 0x05, 0x10, 0x03, 0x59, 0x31, 0x38, 0x33, 0x36, 0x34, 0x31, 0x20, 0x20, 0x02, 0x07, 0x01, 0x00, 0x20, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x05, 0x00, 0x00, 0x38,
+    ];
+
+    #[test]
+    fn test_cipher_roundtrip() {
+        static TEST_PAYLOAD: &[u8] = &[1, 170, 170];
+        let cipher = Cipher::from_type0_bytes(TYPE0_RESPONSE);
+        let encrypted = cipher.encrypt(TEST_PAYLOAD);
+        assert_eq!(&cipher.decrypt(&encrypted)[..TEST_PAYLOAD.len()], TEST_PAYLOAD);
+    }
+}
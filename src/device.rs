@@ -0,0 +1,80 @@
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, fmt};
+
+#[derive(Serialize, Deserialize)]
+pub struct SoftwareVersion {
+    pub major: usize,
+    pub minor: usize,
+    pub revision: usize,
+}
+
+impl fmt::Display for SoftwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.revision)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProductType {
+    Neptune,
+    Wave,
+    Tracker,
+    DataLogger,
+    N3,
+    N3A,
+    Atlas,
+    Unknown,
+}
+
+impl From<u8> for ProductType {
+    fn from(code: u8) -> Self {
+        match code {
+            1 => ProductType::Neptune,
+            2 => ProductType::Wave,
+            3 => ProductType::Tracker,
+            4 => ProductType::DataLogger,
+            5 => ProductType::N3,
+            6 => ProductType::N3A,
+            7 => ProductType::Atlas,
+            _ => ProductType::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub sw_version: SoftwareVersion,
+    pub serial_number: String,
+    pub hardware_revision: u8,
+    pub product_type: ProductType,
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Alti-2 {:?} (rev. {}, S/N {}, S/W {})",
+            self.product_type, self.hardware_revision, self.serial_number, self.sw_version
+        )
+    }
+}
+
+impl TryFrom<&[u8]> for DeviceInfo {
+    type Error = Error;
+
+    /// Parses an already checksum-verified Info frame. The caller (`Response::from_frame`) is
+    /// responsible for validating the checksum before handing us the bytes.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sw_version: SoftwareVersion {
+                major: (bytes[3] >> 4) as usize,
+                minor: (bytes[3] & 0x0f) as usize,
+                revision: bytes[4] as usize,
+            },
+            serial_number: String::from_utf8(bytes[5..14].to_vec())?.trim().to_string(),
+            hardware_revision: bytes[14],
+            product_type: ProductType::from(bytes[15]),
+        })
+    }
+}
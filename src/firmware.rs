@@ -0,0 +1,278 @@
+use anyhow::{bail, ensure, Error};
+use std::convert::TryInto;
+use thiserror::Error as ThisError;
+
+use crate::{
+    device::{DeviceInfo, ProductType},
+    protocol::{checksum, Command},
+    session::Session,
+    transport::Transport,
+};
+
+const MAGIC: &[u8; 4] = b"AL2F";
+const HEADER_LEN: usize = MAGIC.len() + 2; // magic + product type byte + hardware revision byte
+const TRAILER_LEN: usize = 4; // little-endian word-sum checksum over the body
+
+// `frame_record` encodes the record length in a single byte, so a chunk can never be larger
+// than a `u8` can hold.
+const RECORD_SIZE: usize = 255;
+const MAX_RECORD_RETRIES: u8 = 3;
+
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+
+/// Why a firmware image was rejected before any bytes were sent to the device.
+#[derive(Debug, ThisError)]
+pub enum FirmwareError {
+    #[error("image is too short to contain a header and checksum trailer")]
+    Truncated,
+    #[error("image is missing the \"AL2F\" magic prefix")]
+    MissingMagic,
+    #[error("image targets {expected:?} but the connected device is {actual:?}")]
+    WrongProduct { expected: ProductType, actual: ProductType },
+    #[error(
+        "image targets hardware revision {expected} but the connected device is revision {actual}"
+    )]
+    WrongHardwareRevision { expected: u8, actual: u8 },
+    #[error("image checksum does not match its trailer")]
+    InvalidChecksum,
+}
+
+/// Checks the image the way the device's own loader would: magic prefix, matching product
+/// header, and a 32-bit little-endian word-sum checksum over the body against its trailer.
+fn validate_image(image: &[u8], device_info: &DeviceInfo) -> Result<(), Error> {
+    ensure!(image.len() >= HEADER_LEN + TRAILER_LEN, FirmwareError::Truncated);
+    if image[..MAGIC.len()] != MAGIC[..] {
+        bail!(FirmwareError::MissingMagic);
+    }
+
+    let expected_product = ProductType::from(image[MAGIC.len()]);
+    if expected_product != device_info.product_type {
+        bail!(FirmwareError::WrongProduct {
+            expected: expected_product,
+            actual: device_info.product_type,
+        });
+    }
+
+    let expected_hardware_revision = image[MAGIC.len() + 1];
+    if expected_hardware_revision != device_info.hardware_revision {
+        bail!(FirmwareError::WrongHardwareRevision {
+            expected: expected_hardware_revision,
+            actual: device_info.hardware_revision,
+        });
+    }
+
+    let body = &image[HEADER_LEN..image.len() - TRAILER_LEN];
+    let trailer = &image[image.len() - TRAILER_LEN..];
+    let expected_checksum = u32::from_le_bytes(trailer.try_into().unwrap());
+
+    let word_sum = body
+        .chunks(4)
+        .map(|chunk| {
+            let mut b = [0u8; 4];
+            b[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(b)
+        })
+        .fold(0u32, |acc, word| acc.wrapping_add(word));
+
+    if word_sum != expected_checksum {
+        bail!(FirmwareError::InvalidChecksum);
+    }
+
+    Ok(())
+}
+
+impl<T: Transport> Session<T> {
+    /// Flashes `image` onto the device: validates it, puts the unit into bootloader mode, and
+    /// streams the body in fixed-size, checksummed records, retrying a dropped record a bounded
+    /// number of times before aborting so a single bad byte doesn't brick the unit.
+    pub fn flash_firmware(&mut self, image: &[u8]) -> Result<(), Error> {
+        validate_image(image, &self.device_info)?;
+
+        self.tty.write_all(&Command::EnterBootloader.to_bytes())?;
+        self.read_ack()?;
+
+        let body = &image[HEADER_LEN..image.len() - TRAILER_LEN];
+        for record in body.chunks(RECORD_SIZE) {
+            self.send_record_with_retries(record)?;
+        }
+
+        Ok(())
+    }
+
+    fn send_record_with_retries(&mut self, record: &[u8]) -> Result<(), Error> {
+        let frame = frame_record(record);
+
+        for attempt in 0..=MAX_RECORD_RETRIES {
+            self.tty.write_all(&frame)?;
+            match self.read_ack() {
+                Ok(()) => return Ok(()),
+                Err(_) if attempt < MAX_RECORD_RETRIES => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        unreachable!("loop above always returns")
+    }
+
+    fn read_ack(&mut self) -> Result<(), Error> {
+        let mut byte = [0u8; 1];
+        self.tty.read_exact(&mut byte)?;
+        match byte[0] {
+            ACK => Ok(()),
+            NAK => bail!("device NAK'd the record"),
+            other => bail!("unexpected response byte {:#04x} while waiting for ACK/NAK", other),
+        }
+    }
+}
+
+/// Frames a firmware record for the bulk transfer: unlike `Command::to_bytes`, which
+/// ASCII-hex-encodes a command frame, this is raw binary — a length byte, the payload, and a
+/// trailing `wrapping_add` checksum.
+fn frame_record(record: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(record.len() + 2);
+    bytes.push(record.len() as u8);
+    bytes.extend_from_slice(record);
+    bytes.push(checksum(record));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::Cipher;
+    use crate::transport::MockTransport;
+    use std::convert::TryFrom;
+
+    static TYPE0_RESPONSE: &[u8] = &[
+        0x1E, 0x00, 0x05, 0x10, 0x03, 0x59, 0x31, 0x38, 0x33, 0x36, 0x34, 0x31, 0x20, 0x20, 0x02, 0x07, 0x01, 0x00, 0x20, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x05, 0x00, 0x00, 0x38,
+    ];
+
+    fn device_info() -> DeviceInfo {
+        DeviceInfo::try_from(TYPE0_RESPONSE).unwrap()
+    }
+
+    // product byte 0x07 (Atlas) and hardware revision 0x02 match `device_info()`; a 4-byte
+    // all-zero body checksums to 0, so the trailer is zero too.
+    fn valid_image() -> Vec<u8> {
+        let mut image = Vec::new();
+        image.extend_from_slice(MAGIC);
+        image.push(0x07);
+        image.push(0x02);
+        image.extend_from_slice(&[0, 0, 0, 0]);
+        image.extend_from_slice(&[0, 0, 0, 0]);
+        image
+    }
+
+    #[test]
+    fn test_validate_image_accepts_matching_image() {
+        assert!(validate_image(&valid_image(), &device_info()).is_ok());
+    }
+
+    #[test]
+    fn test_validate_image_rejects_truncated_image() {
+        let image = &valid_image()[..HEADER_LEN + TRAILER_LEN - 1];
+        let err = validate_image(image, &device_info()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FirmwareError>(), Some(FirmwareError::Truncated)));
+    }
+
+    #[test]
+    fn test_validate_image_rejects_missing_magic() {
+        let mut image = valid_image();
+        image[..MAGIC.len()].copy_from_slice(b"XXXX");
+        let err = validate_image(&image, &device_info()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FirmwareError>(), Some(FirmwareError::MissingMagic)));
+    }
+
+    #[test]
+    fn test_validate_image_rejects_wrong_product() {
+        let mut image = valid_image();
+        image[MAGIC.len()] = 0x01; // a different ProductType
+        let err = validate_image(&image, &device_info()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FirmwareError>(), Some(FirmwareError::WrongProduct { .. })));
+    }
+
+    #[test]
+    fn test_validate_image_rejects_wrong_hardware_revision() {
+        let mut image = valid_image();
+        image[MAGIC.len() + 1] = 0x09; // same product, different hardware revision
+        let err = validate_image(&image, &device_info()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FirmwareError>(),
+            Some(FirmwareError::WrongHardwareRevision { expected: 0x09, actual: 0x02 })
+        ));
+    }
+
+    #[test]
+    fn test_validate_image_rejects_invalid_checksum() {
+        let mut image = valid_image();
+        let len = image.len();
+        image[len - 4] = 0x01; // trailer no longer matches the all-zero body
+        let err = validate_image(&image, &device_info()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<FirmwareError>(), Some(FirmwareError::InvalidChecksum)));
+    }
+
+    #[test]
+    fn test_flash_firmware_retries_a_nakd_record() {
+        let frame = frame_record(&[0, 0, 0, 0]);
+        let mut responses = vec![ACK, NAK];
+        responses.extend_from_slice(&[ACK]);
+
+        let mut session = Session {
+            tty: MockTransport::new(responses),
+            device_info: device_info(),
+            cipher: Cipher::from_type0_bytes(TYPE0_RESPONSE),
+        };
+
+        session.flash_firmware(&valid_image()).unwrap();
+
+        let mut expected_writes = Command::EnterBootloader.to_bytes();
+        expected_writes.extend_from_slice(&frame);
+        expected_writes.extend_from_slice(&frame);
+        assert_eq!(session.tty.written, expected_writes);
+    }
+
+    #[test]
+    fn test_flash_firmware_splits_a_full_record_size_chunk_correctly() {
+        // An all-zero body one byte longer than RECORD_SIZE: one full-size chunk, one 1-byte
+        // chunk. Checksums to zero either way, so the trailer stays all-zero.
+        let body = vec![0u8; RECORD_SIZE + 1];
+        let mut image = Vec::new();
+        image.extend_from_slice(MAGIC);
+        image.push(0x07);
+        image.push(0x02);
+        image.extend_from_slice(&body);
+        image.extend_from_slice(&[0, 0, 0, 0]);
+
+        let full_chunk_frame = frame_record(&vec![0u8; RECORD_SIZE]);
+        let remainder_frame = frame_record(&[0u8]);
+        assert_eq!(full_chunk_frame[0], RECORD_SIZE as u8);
+        assert_eq!(remainder_frame[0], 1);
+
+        let mut session = Session {
+            tty: MockTransport::new(vec![ACK, ACK, ACK]),
+            device_info: device_info(),
+            cipher: Cipher::from_type0_bytes(TYPE0_RESPONSE),
+        };
+
+        session.flash_firmware(&image).unwrap();
+
+        let mut expected_writes = Command::EnterBootloader.to_bytes();
+        expected_writes.extend_from_slice(&full_chunk_frame);
+        expected_writes.extend_from_slice(&remainder_frame);
+        assert_eq!(session.tty.written, expected_writes);
+    }
+
+    #[test]
+    fn test_flash_firmware_gives_up_after_max_retries() {
+        let responses = vec![ACK, NAK, NAK, NAK, NAK];
+
+        let mut session = Session {
+            tty: MockTransport::new(responses),
+            device_info: device_info(),
+            cipher: Cipher::from_type0_bytes(TYPE0_RESPONSE),
+        };
+
+        assert!(session.flash_firmware(&valid_image()).is_err());
+    }
+}
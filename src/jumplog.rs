@@ -0,0 +1,64 @@
+use anyhow::{ensure, Error};
+use serde::{Deserialize, Serialize};
+
+/// A single decoded jump/dive record, decrypted off the device's log.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JumpLog {
+    pub exit_altitude_ft: u16,
+    pub freefall_time_secs: u16,
+    pub canopy_time_secs: u16,
+    pub max_speed_mph: u16,
+    pub timestamp: u32,
+}
+
+impl JumpLog {
+    /// Size, in plaintext bytes, of one record as laid out by the device. Only the first 12
+    /// bytes hold decoded fields; the record's reserved trailing bytes are left unparsed.
+    pub(crate) const SIZE: usize = 16;
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        ensure!(bytes.len() >= Self::SIZE, "truncated jump log record");
+        Ok(Self {
+            exit_altitude_ft: u16::from_le_bytes([bytes[0], bytes[1]]),
+            freefall_time_secs: u16::from_le_bytes([bytes[2], bytes[3]]),
+            canopy_time_secs: u16::from_le_bytes([bytes[4], bytes[5]]),
+            max_speed_mph: u16::from_le_bytes([bytes[6], bytes[7]]),
+            timestamp: u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::Cipher;
+
+    static TYPE0_RESPONSE: &[u8] = &[
+        0x1E, 0x00, 0x05, 0x10, 0x03, 0x59, 0x31, 0x38, 0x33, 0x36, 0x34, 0x31, 0x20, 0x20, 0x02, 0x07, 0x01, 0x00, 0x20, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x05, 0x00, 0x00, 0x38,
+    ];
+
+    // A single 32-byte encrypted log record block, as the device would return it from a
+    // GetLogRecord reply.
+    static ENCRYPTED_RESPONSE: &[u8] = &[
+        0xd0, 0x88, 0x00, 0x6f, 0x8d, 0xf9, 0x69, 0xd9, 0xa1, 0xee, 0x34, 0xba, 0x20, 0xc3, 0xf4, 0x81,
+        0x3b, 0x97, 0x27, 0xfe, 0xe0, 0x2a, 0x8f, 0x66, 0x3b, 0x97, 0x27, 0xfe, 0xe0, 0x2a, 0x8f, 0x66,
+    ];
+
+    #[test]
+    fn test_decrypts_known_record() {
+        let cipher = Cipher::from_type0_bytes(TYPE0_RESPONSE);
+        let decrypted = cipher.decrypt(ENCRYPTED_RESPONSE);
+        let record = JumpLog::from_bytes(&decrypted).unwrap();
+
+        assert_eq!(
+            record,
+            JumpLog {
+                exit_altitude_ft: 13500,
+                freefall_time_secs: 60,
+                canopy_time_secs: 300,
+                max_speed_mph: 120,
+                timestamp: 1700000000,
+            }
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod cipher;
+pub mod device;
+pub mod firmware;
+pub mod jumplog;
+pub mod protocol;
+pub mod session;
+pub mod transport;
@@ -0,0 +1,146 @@
+use anyhow::{bail, ensure, Error};
+use std::{convert::TryFrom, io::Read};
+use thiserror::Error as ThisError;
+
+use crate::device::DeviceInfo;
+
+pub(crate) fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |acc, b| acc.wrapping_add(*b))
+}
+
+/// Reads one length-prefixed `"XX "` ASCII-hex frame off the wire: a length byte, that many
+/// payload bytes, one checksum byte, and a trailing `"\r\n"`.
+pub(crate) fn read_frame(tty: &mut impl Read) -> Result<Vec<u8>, Error> {
+    let mut buf = [0u8; 1024];
+
+    // Read just the length (in "XX " hex ASCII format).
+    tty.read_exact(&mut buf[..3])?;
+    let len = hex::decode(String::from_utf8(buf[0..2].to_vec())?)?[0] as usize;
+
+    // (len+1) to include the checksum byte, multiply by 3 for each "XX " spaced combination of hex,
+    // then add 2 for the "\r\n" ending.
+    let remaining_ascii_len = (len + 1) * 3 + 2;
+    tty.read_exact(&mut buf[3..3 + remaining_ascii_len])?;
+
+    let info_str = String::from_utf8(buf[..2 + remaining_ascii_len].to_vec())?;
+    let stripped_str = info_str.replace(&[' ', '\n', '\r'][..], "");
+    let info_bytes = hex::decode(&stripped_str)?;
+
+    Ok(info_bytes)
+}
+
+/// Error codes the device reports back in an Exception frame, keyed off the status byte that
+/// follows the message-type byte.
+#[derive(Debug, ThisError)]
+pub enum DeviceException {
+    #[error("device reported an unsupported command")]
+    Unsupported,
+    #[error("device is busy and rejected the request")]
+    Busy,
+    #[error("device rejected the command's arguments")]
+    InvalidArgument,
+    #[error("device raised an unrecognized exception code {0:#04x}")]
+    Unknown(u8),
+}
+
+impl From<u8> for DeviceException {
+    fn from(code: u8) -> Self {
+        match code {
+            0x01 => DeviceException::Unsupported,
+            0x02 => DeviceException::Busy,
+            0x03 => DeviceException::InvalidArgument,
+            _ => DeviceException::Unknown(code),
+        }
+    }
+}
+
+/// The leading byte of every response's payload, identifying how the remaining bytes (and any
+/// exception code) should be interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageType {
+    Info,
+    LogIndex,
+    LogRecord,
+    Settings,
+    Exception,
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = Error;
+
+    fn try_from(byte: u8) -> Result<Self, Error> {
+        Ok(match byte {
+            0x00 => MessageType::Info,
+            0x01 => MessageType::LogIndex,
+            0x02 => MessageType::LogRecord,
+            0x03 => MessageType::Settings,
+            0x7f => MessageType::Exception,
+            other => bail!("unrecognized response type byte {:#04x}", other),
+        })
+    }
+}
+
+/// The device's command set. Each variant encodes to a single command byte (plus any
+/// arguments) and is framed by `to_bytes` exactly as the device expects it.
+pub enum Command {
+    GetInfo,
+    GetLogIndex,
+    GetLogRecord { index: u16 },
+    GetSettings,
+    EnterBootloader,
+}
+
+impl Command {
+    fn contents(&self) -> Vec<u8> {
+        match self {
+            Command::GetInfo => vec![0x80],
+            Command::GetLogIndex => vec![0x81],
+            Command::GetLogRecord { index } => {
+                let [lo, hi] = index.to_le_bytes();
+                vec![0x82, lo, hi]
+            }
+            Command::GetSettings => vec![0x83],
+            Command::EnterBootloader => vec![0x90],
+        }
+    }
+
+    /// Handles all the weird formatting the device expects. Ex: turns the 0x80 command into
+    /// the string "018080", prepending the length and appending the checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let contents = self.contents();
+        let mut bytes = vec![];
+        bytes.extend_from_slice(hex::encode_upper([contents.len() as u8]).as_bytes());
+        bytes.extend_from_slice(hex::encode_upper(&contents).as_bytes());
+        bytes.extend_from_slice(hex::encode_upper([checksum(&contents)]).as_bytes());
+        bytes
+    }
+}
+
+/// A decoded, checksum-verified reply to a `Command`.
+pub enum Response {
+    Info(DeviceInfo),
+    LogIndex(Vec<u8>),
+    LogRecord(Vec<u8>),
+    Settings(Vec<u8>),
+}
+
+impl Response {
+    /// Validates the trailing checksum on a raw frame (as returned by `read_frame`) and
+    /// dispatches parsing by its message-type byte. Exception frames are turned into `Err`
+    /// rather than handed back as a `Response` variant.
+    pub(crate) fn from_frame(bytes: &[u8]) -> Result<Self, Error> {
+        ensure!(bytes.len() >= 3, "frame too short to contain a response");
+        ensure!(
+            checksum(&bytes[1..bytes.len() - 1]) == bytes[bytes.len() - 1],
+            "checksum mismatch"
+        );
+
+        match MessageType::try_from(bytes[1])? {
+            MessageType::Info => Ok(Response::Info(DeviceInfo::try_from(bytes)?)),
+            MessageType::LogIndex => Ok(Response::LogIndex(bytes[2..bytes.len() - 1].to_vec())),
+            MessageType::LogRecord => Ok(Response::LogRecord(bytes[2..bytes.len() - 1].to_vec())),
+            MessageType::Settings => Ok(Response::Settings(bytes[2..bytes.len() - 1].to_vec())),
+            MessageType::Exception => Err(DeviceException::from(bytes[2]).into()),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::{bail, ensure, Context, Error};
+use serial::{
+    core::{SerialDevice, SerialPortSettings},
+    BaudRate, CharSize, FlowControl, Parity, StopBits,
+};
+use std::{path::Path, thread::sleep, time::Duration};
+
+use crate::{
+    cipher::Cipher,
+    device::DeviceInfo,
+    jumplog::JumpLog,
+    protocol::{checksum, read_frame, Command, Response},
+    transport::Transport,
+};
+
+static TTY_TIMEOUT: Duration = Duration::from_millis(10000);
+static PAUSE_BEFORE_HANDSHAKE: Duration = Duration::from_secs(10);
+const DEFAULT_HANDSHAKE_RETRIES: u32 = 3;
+
+/// Builder for the serial settings and handshake behavior `Session::open` uses, so the
+/// hard-coded baud rate, timeout, and retry count can be overridden per device/platform.
+pub struct SessionConfig {
+    path: String,
+    baud_rate: BaudRate,
+    timeout: Duration,
+    pause_before_handshake: Duration,
+    handshake_retries: u32,
+}
+
+impl SessionConfig {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            baud_rate: BaudRate::Baud57600,
+            timeout: TTY_TIMEOUT,
+            pause_before_handshake: PAUSE_BEFORE_HANDSHAKE,
+            handshake_retries: DEFAULT_HANDSHAKE_RETRIES,
+        }
+    }
+
+    pub fn baud_rate(mut self, baud_rate: BaudRate) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn pause_before_handshake(mut self, pause_before_handshake: Duration) -> Self {
+        self.pause_before_handshake = pause_before_handshake;
+        self
+    }
+
+    pub fn handshake_retries(mut self, handshake_retries: u32) -> Self {
+        self.handshake_retries = handshake_retries;
+        self
+    }
+
+    /// Opens the TTY, asserts DTR, waits `pause_before_handshake`, and then runs the type-0
+    /// handshake retry loop (`handshake`), making up to `handshake_retries + 1` attempts.
+    pub fn open(self) -> Result<Session<serial::unix::TTYPort>, Error> {
+        let mut tty = serial::unix::TTYPort::open(Path::new(&self.path))
+            .with_context(|| format!("failed to open {}", self.path))?;
+        tty.set_timeout(self.timeout)?;
+        let mut settings = tty.read_settings()?;
+        settings.set_baud_rate(self.baud_rate)?;
+        settings.set_char_size(CharSize::Bits8);
+        settings.set_stop_bits(StopBits::Stop1);
+        settings.set_parity(Parity::ParityNone);
+        settings.set_flow_control(FlowControl::FlowHardware);
+        tty.write_settings(&settings)?;
+        tty.set_dtr(true)?;
+
+        sleep(self.pause_before_handshake);
+        let type0_bytes = Self::handshake(&mut tty, self.handshake_retries)?;
+        let device_info = match Response::from_frame(&type0_bytes)? {
+            Response::Info(info) => info,
+            _ => bail!("device responded to GetInfo with a non-Info frame"),
+        };
+        let cipher = Cipher::from_type0_bytes(&type0_bytes);
+        Ok(Session { tty, device_info, cipher })
+    }
+
+    /// Polls the device for its ready state by repeatedly attempting the type-0 handshake,
+    /// retrying on timeout or checksum failure with linear backoff before giving up.
+    fn handshake(tty: &mut serial::unix::TTYPort, retries: u32) -> Result<Vec<u8>, Error> {
+        let mut last_err = None;
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                sleep(Duration::from_secs(attempt as u64));
+            }
+            match Session::get_type0(tty) {
+                Ok(bytes) => return Ok(bytes),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap()).with_context(|| format!("handshake failed after {} attempts", retries + 1))
+    }
+}
+
+/// A handshaken connection to the device, generic over its `Transport` so the same command/log
+/// flows can run against either the real serial port or an in-memory mock in tests.
+pub struct Session<T: Transport> {
+    pub(crate) tty: T,
+    pub device_info: DeviceInfo,
+    pub(crate) cipher: Cipher,
+}
+
+impl Session<serial::unix::TTYPort> {
+    pub fn open(path: &str) -> Result<Self, Error> {
+        SessionConfig::new(path).open()
+    }
+}
+
+impl<T: Transport> Session<T> {
+    fn get_type0(tty: &mut T) -> Result<Vec<u8>, Error> {
+        tty.write_all(&Command::GetInfo.to_bytes())?;
+        sleep(Duration::from_millis(100));
+        let bytes = read_frame(tty)?;
+        ensure!(bytes.len() >= 2, "frame too short to contain a response");
+        ensure!(
+            checksum(&bytes[1..bytes.len() - 1]) == bytes[bytes.len() - 1],
+            "checksum mismatch"
+        );
+        Ok(bytes)
+    }
+
+    /// Writes `cmd` to the device, reads back its length-prefixed frame, and parses it into a
+    /// `Response`. Exception replies surface as `Err` rather than a `Response` variant.
+    pub fn transact(&mut self, cmd: Command) -> Result<Response, Error> {
+        self.tty.write_all(&cmd.to_bytes())?;
+        sleep(Duration::from_millis(100));
+        let frame = read_frame(&mut self.tty)?;
+        Response::from_frame(&frame)
+    }
+
+    /// Reads the device's jump/dive log index, downloading and decrypting every record.
+    ///
+    /// Records come back encrypted across one or more `GetLogRecord` replies, in 32-byte
+    /// aligned chunks matching `Cipher::decrypt`'s own padding behavior. The device zero-pads
+    /// the final block out to that boundary, so the record count from `GetLogIndex` (rather
+    /// than the decrypted buffer's length) is what tells us where the real records end.
+    pub fn download_logs(&mut self) -> Result<Vec<JumpLog>, Error> {
+        let record_count = self.log_record_count()?;
+
+        let mut encrypted = Vec::new();
+        for index in 0..record_count {
+            match self.transact(Command::GetLogRecord { index })? {
+                Response::LogRecord(bytes) => encrypted.extend_from_slice(&bytes),
+                _ => bail!("device responded to GetLogRecord with an unexpected frame"),
+            }
+        }
+
+        let decrypted = self.cipher.decrypt(&encrypted);
+
+        decrypted
+            .chunks(JumpLog::SIZE)
+            .take(record_count as usize)
+            .map(JumpLog::from_bytes)
+            .collect()
+    }
+
+    fn log_record_count(&mut self) -> Result<u16, Error> {
+        match self.transact(Command::GetLogIndex)? {
+            Response::LogIndex(bytes) => {
+                ensure!(bytes.len() >= 2, "log index frame too short to contain a record count");
+                Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+            }
+            _ => bail!("device responded to GetLogIndex with an unexpected frame"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::ProductType;
+    use crate::transport::MockTransport;
+    use std::convert::TryFrom;
+
+    static TYPE0_RESPONSE: &[u8] = &[
+        0x1E, 0x00, 0x05, 0x10, 0x03, 0x59, 0x31, 0x38, 0x33, 0x36, 0x34, 0x31, 0x20, 0x20, 0x02, 0x07, 0x01, 0x00, 0x20, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x05, 0x00, 0x00, 0x38,
+    ];
+
+    fn type0_frame_ascii() -> Vec<u8> {
+        let mut ascii = Vec::new();
+        for byte in TYPE0_RESPONSE {
+            ascii.extend_from_slice(hex::encode_upper([*byte]).as_bytes());
+            ascii.push(b' ');
+        }
+        ascii.extend_from_slice(b"\r\n");
+        ascii
+    }
+
+    #[test]
+    fn test_transact_parses_info_response() {
+        let mut session = Session {
+            tty: MockTransport::new(type0_frame_ascii()),
+            device_info: DeviceInfo::try_from(TYPE0_RESPONSE).unwrap(),
+            cipher: Cipher::from_type0_bytes(TYPE0_RESPONSE),
+        };
+
+        let response = session.transact(Command::GetInfo).unwrap();
+        let info = match response {
+            Response::Info(info) => info,
+            _ => panic!("expected an Info response"),
+        };
+
+        assert_eq!(info.product_type, ProductType::Atlas);
+        assert_eq!(session.tty.written, Command::GetInfo.to_bytes());
+    }
+}
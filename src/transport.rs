@@ -0,0 +1,57 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+};
+
+/// Anything `Session` can write framed commands to and read framed responses from. The real
+/// serial port implements this via its own `Read`/`Write` impls; `MockTransport` below replays
+/// canned frames so the session can be driven end-to-end without hardware.
+pub trait Transport: Read + Write {}
+
+impl<T: Read + Write> Transport for T {}
+
+/// An in-memory transport that records everything written to it and replays a queue of canned
+/// response bytes, for exercising `Session` in tests.
+#[derive(Default)]
+pub struct MockTransport {
+    pub written: Vec<u8>,
+    responses: VecDeque<u8>,
+}
+
+impl MockTransport {
+    pub fn new(responses: impl IntoIterator<Item = u8>) -> Self {
+        Self { written: Vec::new(), responses: responses.into_iter().collect() }
+    }
+}
+
+impl Read for MockTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.responses.pop_front() {
+                Some(byte) => {
+                    buf[read] = byte;
+                    read += 1;
+                }
+                None => break,
+            }
+        }
+
+        if read == 0 && !buf.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "mock transport exhausted"));
+        }
+
+        Ok(read)
+    }
+}
+
+impl Write for MockTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}